@@ -0,0 +1,170 @@
+//! A `proptest` generator for arbitrary well-formed circuits, behind the `proptest` feature.
+
+use super::{Circuit, Component, ComponentKind};
+use petgraph::{graph::NodeIndex, Outgoing};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+/// One generated gate, with its operands expressed as raw integers rather than concrete node
+/// indices. Each one is reduced modulo the size of the pool of earlier nodes still eligible as
+/// operands when the circuit is built, so it always refers to something that already exists -
+/// this is what keeps every generated circuit acyclic, since a gate can only ever reach back into earlier nodes.
+#[derive(Debug, Clone)]
+enum GateSpec {
+    Not(usize),
+    And(Vec<usize>),
+    Or(Vec<usize>),
+}
+
+#[derive(Debug, Clone)]
+struct CircuitSpec {
+    input_count: usize,
+    gates: Vec<GateSpec>,
+    output_picks: Vec<usize>,
+}
+
+fn gate_spec_strategy() -> impl Strategy<Value = GateSpec> {
+    prop_oneof![
+        any::<usize>().prop_map(GateSpec::Not),
+        prop::collection::vec(any::<usize>(), 2..=3).prop_map(GateSpec::And),
+        prop::collection::vec(any::<usize>(), 2..=3).prop_map(GateSpec::Or),
+    ]
+}
+
+fn circuit_spec_strategy() -> impl Strategy<Value = CircuitSpec> {
+    (
+        1..=4_usize,
+        prop::collection::vec(gate_spec_strategy(), 0..=8),
+        prop::collection::vec(any::<usize>(), 1..=4),
+    )
+        .prop_map(|(input_count, gates, output_picks)| CircuitSpec {
+            input_count,
+            gates,
+            output_picks,
+        })
+}
+
+/// A `Component` isn't `Clone`, so the pool tracks the raw index instead and rebuilds a component
+/// handle on demand - the same trick `rewrite` uses to hand `petgraph` indices back to `Circuit`'s
+/// public connection API.
+fn wrap(index: NodeIndex) -> Component {
+    Component { index }
+}
+
+/// A `Not` gate may only ever have a single consumer, so once one is used as an operand (or given
+/// an output of its own) it has to be taken out of circulation. Everything else can fan out freely,
+/// so it stays in the pool for later gates and outputs to pick from as well.
+fn take_operand(pool: &mut Vec<(NodeIndex, ComponentKind)>, raw_index: usize) -> NodeIndex {
+    let position = raw_index % pool.len();
+    let (index, kind) = pool[position];
+    if matches!(kind, ComponentKind::Not) {
+        pool.remove(position);
+    }
+    index
+}
+
+/// Turns a `CircuitSpec` into a real `Circuit`, resolving every raw operand index against the pool
+/// of nodes that are still eligible to be wired up to something else.
+fn build_circuit(spec: &CircuitSpec) -> Circuit {
+    let mut circuit = Circuit::new();
+    let mut pool: Vec<(NodeIndex, ComponentKind)> = Vec::new();
+
+    for i in 0..spec.input_count {
+        let input = circuit.add_component(format!("I{}", i), ComponentKind::Input);
+        pool.push((input.index, ComponentKind::Input));
+    }
+
+    for (gate_number, gate) in spec.gates.iter().enumerate() {
+        let (kind, raw_operands) = match gate {
+            GateSpec::Not(index) => (ComponentKind::Not, vec![*index]),
+            GateSpec::And(indices) => (ComponentKind::And, indices.clone()),
+            GateSpec::Or(indices) => (ComponentKind::Or, indices.clone()),
+        };
+
+        let gate_component = circuit.add_component(format!("G{}", gate_number), kind);
+        for raw_index in raw_operands {
+            let operand = take_operand(&mut pool, raw_index);
+            circuit.add_connection(&wrap(operand), &gate_component);
+        }
+
+        let index = gate_component.index;
+        pool.push((index, kind));
+    }
+
+    // Every gate needs at least one consumer or `validate` will reject it, and a `Not` gate needs
+    // exactly one. Anything still sitting in the pool hasn't been wired up to anything, so union
+    // the randomly picked outputs with whichever pool entries still need a consumer - `Not`
+    // entries always do (that's why they're still here), `And`/`Or` only if nothing claimed them.
+    let mut output_positions: HashSet<usize> = spec
+        .output_picks
+        .iter()
+        .map(|index| index % pool.len())
+        .collect();
+
+    for (position, &(index, kind)) in pool.iter().enumerate() {
+        let needs_output = match kind {
+            ComponentKind::Not => true,
+            ComponentKind::And | ComponentKind::Or => circuit
+                .graph()
+                .neighbors_directed(index, Outgoing)
+                .next()
+                .is_none(),
+            ComponentKind::Input | ComponentKind::Output => false,
+        };
+
+        if needs_output {
+            output_positions.insert(position);
+        }
+    }
+
+    for (output_number, position) in output_positions.into_iter().enumerate() {
+        let output = circuit.add_component(format!("O{}", output_number), ComponentKind::Output);
+        circuit.add_connection(&wrap(pool[position].0), &output);
+    }
+
+    circuit
+}
+
+/// A `proptest` strategy that generates arbitrary well-formed circuits - every value it produces
+/// satisfies `circuit.validate()` by construction, so it's suitable for roundtrip properties like
+/// "deduplicate preserves the truth table" or "stringify never panics"
+pub fn any_circuit() -> impl Strategy<Value = Circuit> {
+    circuit_spec_strategy().prop_map(|spec| build_circuit(&spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{dot, stringify};
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn generated_circuits_are_always_valid(circuit in any_circuit()) {
+            circuit.validate().unwrap();
+        }
+
+        #[test]
+        fn stringify_never_panics(circuit in any_circuit()) {
+            let _ = stringify::stringify_circuit(&circuit);
+        }
+
+        #[test]
+        fn to_dot_never_panics(circuit in any_circuit()) {
+            let _ = dot::to_dot(&circuit);
+        }
+
+        #[test]
+        fn deduplicate_preserves_the_truth_table(mut circuit in any_circuit()) {
+            let before = circuit.truth_table();
+            circuit.deduplicate();
+            prop_assert_eq!(before, circuit.truth_table());
+        }
+
+        #[test]
+        fn simplify_preserves_the_truth_table(mut circuit in any_circuit()) {
+            let before = circuit.truth_table();
+            circuit.simplify();
+            prop_assert_eq!(before, circuit.truth_table());
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use super::{Component, ComponentData, ComponentKind};
-use petgraph::{graph::NodeIndex, Directed, Graph, Incoming, Outgoing};
+use petgraph::{
+    algo::{tarjan_scc, toposort},
+    graph::NodeIndex,
+    visit::IntoNodeReferences,
+    Directed, Graph, Incoming, Outgoing,
+};
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryInto;
 
 /// The direction of a connection
@@ -39,6 +45,7 @@ pub enum ValidationErrorKind {
     IncorrectInputs,
     IncorrectOutputs,
     DuplicateName,
+    CombinationalCycle,
 }
 
 #[derive(Debug)]
@@ -53,6 +60,30 @@ impl ValidationError {
     }
 }
 
+/// The structural identity of a node, used by `Circuit::deduplicate` to recognize subcircuits
+/// that compute the exact same thing. Two nodes with equal signatures are interchangeable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Signature {
+    Input(String),
+    Not(NodeIndex),
+    /// Children are sorted since `And` is commutative
+    And(Vec<NodeIndex>),
+    /// Children are sorted since `Or` is commutative
+    Or(Vec<NodeIndex>),
+}
+
+/// A full enumeration of a circuit's behavior: every combination of input values alongside the
+/// outputs it produces
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruthTable {
+    /// The input names, in the order their values appear in each row
+    pub input_names: Vec<String>,
+    /// The output names, in the order their values appear in each row
+    pub output_names: Vec<String>,
+    /// One `(inputs, outputs)` pair per combination of input values
+    pub rows: Vec<(Vec<bool>, Vec<bool>)>,
+}
+
 impl Circuit {
     pub fn new() -> Self {
         Self {
@@ -75,6 +106,226 @@ impl Circuit {
         self.graph.add_edge(from.index, to.index, ());
     }
 
+    /// Gives other modules in this crate access to the underlying graph without exposing it
+    /// as part of the public API
+    pub(crate) fn graph(&self) -> &Graph<ComponentData, (), Directed> {
+        &self.graph
+    }
+
+    /// The names of every `Input` component in the circuit, sorted alphabetically
+    fn input_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .graph
+            .node_references()
+            .filter(|(_, data)| matches!(data.kind, ComponentKind::Input))
+            .map(|(_, data)| data.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// The names of every `Output` component in the circuit, sorted alphabetically
+    fn output_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .graph
+            .node_references()
+            .filter(|(_, data)| matches!(data.kind, ComponentKind::Output))
+            .map(|(_, data)| data.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Propagates `inputs` through the circuit and returns the resulting value of every output
+    ///
+    /// Requires the circuit to already satisfy `Circuit::validate` - in particular, every `Not`
+    /// must have exactly one incoming connection. Panics if the circuit contains a combinational
+    /// cycle, a `Not` with no input connected, or if `inputs` is missing a value for one of the
+    /// circuit's `Input` components.
+    pub fn evaluate(&self, inputs: &HashMap<String, bool>) -> HashMap<String, bool> {
+        let order = toposort(&self.graph, None).expect("circuit must be acyclic to evaluate");
+
+        let mut values: HashMap<NodeIndex, bool> = HashMap::with_capacity(order.len());
+        for node in order {
+            let data = &self.graph[node];
+            let mut incoming = self.graph.neighbors_directed(node, Incoming);
+
+            let value = match data.kind {
+                ComponentKind::Input => *inputs
+                    .get(&data.name)
+                    .unwrap_or_else(|| panic!("missing value for input {}", data.name)),
+                ComponentKind::Output => incoming.next().is_some_and(|child| values[&child]),
+                ComponentKind::Not => {
+                    !values[&incoming.next().expect("Not must have exactly one input connected")]
+                }
+                ComponentKind::And => incoming.all(|child| values[&child]),
+                ComponentKind::Or => incoming.any(|child| values[&child]),
+            };
+
+            values.insert(node, value);
+        }
+
+        self.graph
+            .node_references()
+            .filter(|(_, data)| matches!(data.kind, ComponentKind::Output))
+            .map(|(index, data)| (data.name.clone(), values[&index]))
+            .collect()
+    }
+
+    /// Enumerates every combination of input values and records the outputs they produce
+    pub fn truth_table(&self) -> TruthTable {
+        let input_names = self.input_names();
+        let output_names = self.output_names();
+
+        let combinations = 1u64 << input_names.len();
+        let rows = (0..combinations)
+            .map(|combination| {
+                let input_values: Vec<bool> = (0..input_names.len())
+                    .map(|bit| (combination >> bit) & 1 == 1)
+                    .collect();
+
+                let inputs: HashMap<String, bool> = input_names
+                    .iter()
+                    .cloned()
+                    .zip(input_values.iter().copied())
+                    .collect();
+
+                let outputs = self.evaluate(&inputs);
+                let output_values = output_names.iter().map(|name| outputs[name]).collect();
+
+                (input_values, output_values)
+            })
+            .collect();
+
+        TruthTable {
+            input_names,
+            output_names,
+            rows,
+        }
+    }
+
+    /// Checks whether two circuits share the same interface and behave identically on every
+    /// combination of input values
+    pub fn is_equivalent(&self, other: &Circuit) -> bool {
+        self.truth_table() == other.truth_table()
+    }
+
+    /// Merges structurally identical subcircuits so shared logic is computed once
+    ///
+    /// Nodes are visited in topological order and assigned a `Signature` built from their kind
+    /// and the (already-canonicalized) signatures of their children. The first node to produce a
+    /// given signature becomes canonical; every later node with the same signature has its
+    /// outgoing edges redirected to that canonical node and is then removed. `Output` nodes are
+    /// never merged, since each names a distinct observable result.
+    ///
+    /// Requires the circuit to already satisfy `Circuit::validate` - in particular, every `Not`
+    /// must have exactly one incoming connection. Panics if the circuit contains a combinational
+    /// cycle or a `Not` with no input connected.
+    pub fn deduplicate(&mut self) {
+        let topo_order = toposort(&self.graph, None).expect("circuit must be acyclic to deduplicate");
+
+        let mut canonical_index: HashMap<NodeIndex, NodeIndex> = HashMap::with_capacity(topo_order.len());
+        let mut seen: HashMap<Signature, NodeIndex> = HashMap::new();
+        let mut duplicates = Vec::new();
+
+        for node in topo_order {
+            let data = &self.graph[node];
+
+            let signature = match data.kind {
+                ComponentKind::Output => None,
+                ComponentKind::Input => Some(Signature::Input(data.name.clone())),
+                ComponentKind::Not => {
+                    let child = self
+                        .graph
+                        .neighbors_directed(node, Incoming)
+                        .next()
+                        .expect("Not must have exactly one input connected");
+                    Some(Signature::Not(canonical_index[&child]))
+                }
+                ComponentKind::And | ComponentKind::Or => {
+                    let mut children: Vec<NodeIndex> = self
+                        .graph
+                        .neighbors_directed(node, Incoming)
+                        .map(|child| canonical_index[&child])
+                        .collect();
+                    children.sort();
+
+                    Some(if matches!(data.kind, ComponentKind::And) {
+                        Signature::And(children)
+                    } else {
+                        Signature::Or(children)
+                    })
+                }
+            };
+
+            let canonical = match signature {
+                None => node,
+                Some(signature) => match seen.get(&signature) {
+                    Some(&existing) => {
+                        duplicates.push(node);
+                        existing
+                    }
+                    None => {
+                        seen.insert(signature, node);
+                        node
+                    }
+                },
+            };
+
+            canonical_index.insert(node, canonical);
+        }
+
+        for &duplicate in &duplicates {
+            let canonical = canonical_index[&duplicate];
+            let targets: Vec<NodeIndex> = self.graph.neighbors_directed(duplicate, Outgoing).collect();
+
+            for target in targets {
+                self.graph.add_edge(canonical, target, ());
+            }
+        }
+
+        self.remove_nodes(duplicates);
+    }
+
+    /// Removes a batch of nodes from the graph
+    ///
+    /// `Graph::remove_node` moves the last node into the removed slot, so removing nodes in
+    /// descending index order and patching any pending node that gets shifted keeps every
+    /// remaining index in `nodes` pointing at the right node.
+    fn remove_nodes(&mut self, nodes: Vec<NodeIndex>) {
+        let mut remaining = nodes;
+        while let Some(position) = remaining
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, node)| node.index())
+            .map(|(position, _)| position)
+        {
+            let node = remaining.swap_remove(position);
+            let last = NodeIndex::new(self.graph.node_count() - 1);
+
+            self.graph.remove_node(node);
+
+            if last != node {
+                if let Some(shifted) = remaining.iter_mut().find(|n| **n == last) {
+                    *shifted = node;
+                }
+            }
+        }
+    }
+
+    /// Redirects every edge leaving `old_root` to instead leave `replacement`, then removes
+    /// `interior` (which must include `old_root`) now that nothing outside the match still
+    /// depends on those nodes directly
+    pub(crate) fn splice(&mut self, old_root: NodeIndex, replacement: NodeIndex, interior: Vec<NodeIndex>) {
+        let targets: Vec<NodeIndex> = self.graph.neighbors_directed(old_root, Outgoing).collect();
+
+        for target in targets {
+            self.graph.add_edge(replacement, target, ());
+        }
+
+        self.remove_nodes(interior);
+    }
+
     /// Validates the circuit
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
         use petgraph::visit::IntoNodeReferences;
@@ -103,6 +354,21 @@ impl Circuit {
                 });
         }
 
+        // A strongly connected component with more than one node is a feedback loop; a
+        // single-node component is only a cycle if it has an edge back to itself
+        for component in tarjan_scc(&self.graph) {
+            let is_cycle = component.len() > 1 || self.graph.find_edge(component[0], component[0]).is_some();
+
+            if is_cycle {
+                for index in component {
+                    errors.push(ValidationError::new(
+                        ValidationErrorKind::CombinationalCycle,
+                        self.graph[index].clone(),
+                    ));
+                }
+            }
+        }
+
         if errors.len() == 0 {
             Ok(())
         } else {
@@ -341,4 +607,184 @@ mod tests {
 
         validate_errors(errors, &[ValidationErrorKind::DuplicateName]);
     }
+
+    #[test]
+    fn validate_detects_combinational_cycle() {
+        let mut circuit = Circuit::new();
+        let and1 = circuit.add_component("AND_1", ComponentKind::And);
+        let and2 = circuit.add_component("AND_2", ComponentKind::And);
+        let input = circuit.add_component("A", ComponentKind::Input);
+
+        // AND_1 feeds back into AND_2, which feeds back into AND_1
+        circuit.add_connection(&input, &and1);
+        circuit.add_connection(&and2, &and1);
+        circuit.add_connection(&and1, &and2);
+        circuit.add_connection(&input, &and2);
+
+        let errors = circuit
+            .validate()
+            .expect_err("Error expected when the circuit has a feedback loop");
+
+        validate_errors(
+            errors,
+            &[
+                ValidationErrorKind::CombinationalCycle,
+                ValidationErrorKind::CombinationalCycle,
+            ],
+        );
+    }
+
+    fn and_gate_circuit() -> Circuit {
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let input2 = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+
+        circuit.add_connection(&input, &and);
+        circuit.add_connection(&input2, &and);
+        circuit.add_connection(&and, &output);
+
+        circuit
+    }
+
+    #[test]
+    fn evaluate_and_gate() {
+        let circuit = and_gate_circuit();
+
+        let mut inputs = HashMap::new();
+        inputs.insert(String::from("A"), true);
+        inputs.insert(String::from("B"), false);
+        assert_eq!(circuit.evaluate(&inputs), HashMap::from([(String::from("C"), false)]));
+
+        inputs.insert(String::from("B"), true);
+        assert_eq!(circuit.evaluate(&inputs), HashMap::from([(String::from("C"), true)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not must have exactly one input connected")]
+    fn evaluate_panics_on_an_unconnected_not() {
+        let mut circuit = Circuit::new();
+        circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.evaluate(&HashMap::new());
+    }
+
+    #[test]
+    fn truth_table_covers_every_combination() {
+        let circuit = and_gate_circuit();
+        let table = circuit.truth_table();
+
+        assert_eq!(table.input_names, vec![String::from("A"), String::from("B")]);
+        assert_eq!(table.output_names, vec![String::from("C")]);
+        assert_eq!(table.rows.len(), 4);
+
+        let only_true_row = table
+            .rows
+            .iter()
+            .find(|(inputs, _)| inputs == &vec![true, true])
+            .expect("the all-true row should be present");
+        assert_eq!(only_true_row.1, vec![true]);
+    }
+
+    #[test]
+    fn is_equivalent_recognizes_identical_behavior() {
+        let and_circuit = and_gate_circuit();
+
+        // An AND gate built from De Morgan's law: !(!A + !B)
+        let mut morgan_circuit = Circuit::new();
+        let input = morgan_circuit.add_component("A", ComponentKind::Input);
+        let input2 = morgan_circuit.add_component("B", ComponentKind::Input);
+        let output = morgan_circuit.add_component("C", ComponentKind::Output);
+        let not_a = morgan_circuit.add_component("NOT_A", ComponentKind::Not);
+        let not_b = morgan_circuit.add_component("NOT_B", ComponentKind::Not);
+        let or = morgan_circuit.add_component("OR_1", ComponentKind::Or);
+        let not_or = morgan_circuit.add_component("NOT_OR", ComponentKind::Not);
+
+        morgan_circuit.add_connection(&input, &not_a);
+        morgan_circuit.add_connection(&input2, &not_b);
+        morgan_circuit.add_connection(&not_a, &or);
+        morgan_circuit.add_connection(&not_b, &or);
+        morgan_circuit.add_connection(&or, &not_or);
+        morgan_circuit.add_connection(&not_or, &output);
+
+        assert!(and_circuit.is_equivalent(&morgan_circuit));
+    }
+
+    #[test]
+    fn is_equivalent_rejects_different_behavior() {
+        let and_circuit = and_gate_circuit();
+
+        let mut or_circuit = Circuit::new();
+        let input = or_circuit.add_component("A", ComponentKind::Input);
+        let input2 = or_circuit.add_component("B", ComponentKind::Input);
+        let output = or_circuit.add_component("C", ComponentKind::Output);
+        let or = or_circuit.add_component("OR_1", ComponentKind::Or);
+
+        or_circuit.add_connection(&input, &or);
+        or_circuit.add_connection(&input2, &or);
+        or_circuit.add_connection(&or, &output);
+
+        assert!(!and_circuit.is_equivalent(&or_circuit));
+    }
+
+    #[test]
+    fn deduplicate_merges_identical_subcircuits() {
+        // Two outputs that both compute `A + B`, built from separate OR gates
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let input2 = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let output2 = circuit.add_component("D", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+        let or2 = circuit.add_component("OR_2", ComponentKind::Or);
+
+        circuit.add_connection(&input, &or);
+        circuit.add_connection(&input2, &or);
+        circuit.add_connection(&or, &output);
+
+        // Same operands in the opposite order: still the same signature once sorted
+        circuit.add_connection(&input2, &or2);
+        circuit.add_connection(&input, &or2);
+        circuit.add_connection(&or2, &output2);
+
+        let before = circuit.truth_table();
+        circuit.deduplicate();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+
+        let gate_count = circuit
+            .graph()
+            .node_weights()
+            .filter(|data| matches!(data.kind, ComponentKind::Or))
+            .count();
+        assert_eq!(gate_count, 1);
+    }
+
+    #[test]
+    fn deduplicate_preserves_distinct_outputs() {
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+        let output2 = circuit.add_component("C", ComponentKind::Output);
+
+        circuit.add_connection(&input, &output);
+        circuit.add_connection(&input, &output2);
+
+        circuit.deduplicate();
+
+        circuit.validate().unwrap();
+        assert_eq!(circuit.output_names(), vec![String::from("B"), String::from("C")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not must have exactly one input connected")]
+    fn deduplicate_panics_on_an_unconnected_not() {
+        let mut circuit = Circuit::new();
+        circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.deduplicate();
+    }
 }
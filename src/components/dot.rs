@@ -0,0 +1,72 @@
+use super::{Circuit, ComponentKind};
+use petgraph::visit::{EdgeRef, IntoNodeReferences};
+
+/// Renders a circuit as a Graphviz `digraph`, so it can be eyeballed alongside (or instead of)
+/// the text `stringify` output
+pub fn to_dot(circuit: &Circuit) -> String {
+    let graph = circuit.graph();
+
+    let mut lines = vec![String::from("digraph Circuit {")];
+
+    for (index, data) in graph.node_references() {
+        let (shape, color) = style_for(data.kind);
+        lines.push(format!(
+            "    n{} [label=\"{}\", shape={}, style=filled, fillcolor=\"{}\"];",
+            index.index(),
+            escape(&data.name),
+            shape,
+            color
+        ));
+    }
+
+    for edge in graph.edge_references() {
+        lines.push(format!(
+            "    n{} -> n{};",
+            edge.source().index(),
+            edge.target().index()
+        ));
+    }
+
+    lines.push(String::from("}"));
+    lines.join("\n")
+}
+
+/// The Graphviz shape and fill color used to tell components of different kinds apart at a glance
+fn style_for(kind: ComponentKind) -> (&'static str, &'static str) {
+    match kind {
+        ComponentKind::Input => ("invhouse", "lightblue"),
+        ComponentKind::Output => ("house", "lightgreen"),
+        ComponentKind::Not => ("diamond", "lightyellow"),
+        ComponentKind::And => ("box", "lightgray"),
+        ComponentKind::Or => ("ellipse", "lightpink"),
+    }
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ComponentKind;
+    use super::*;
+
+    #[test]
+    fn renders_one_node_per_component_and_one_edge_per_connection() {
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+        let not = circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.add_connection(&input, &not);
+        circuit.add_connection(&not, &output);
+
+        let dot = to_dot(&circuit);
+
+        assert!(dot.starts_with("digraph Circuit {"));
+        assert!(dot.ends_with('}'));
+        assert_eq!(dot.matches("label=").count(), 3);
+        assert_eq!(dot.matches("->").count(), 2);
+        assert!(dot.contains("label=\"NOT_1\", shape=diamond"));
+    }
+}
@@ -1,12 +1,17 @@
 use petgraph::graph::NodeIndex;
 use std::cmp::Ordering;
 
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
 mod circuit;
+pub mod dot;
+pub mod netlist;
+pub mod rewrite;
 pub mod stringify;
 
 use circuit::ConnectionDirection;
 
-pub use circuit::{Circuit, ValidationError, ValidationErrorKind};
+pub use circuit::{Circuit, TruthTable, ValidationError, ValidationErrorKind};
 
 /// A single node in a logic graph
 #[derive(Clone, Copy, Debug)]
@@ -0,0 +1,266 @@
+use super::{Circuit, Component, ComponentKind, ValidationError};
+use petgraph::graph::NodeIndex;
+use std::collections::HashMap;
+
+/// Why a netlist failed to load: a syntax problem pinned to a specific line and column, or a
+/// circuit that parsed fine but didn't pass `Circuit::validate`
+#[derive(Debug)]
+pub enum ParseError {
+    /// A line that doesn't match any recognized statement shape, references an undefined net,
+    /// or drives a net that's already driven
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The netlist parsed, but the circuit it describes isn't valid
+    Invalid(Vec<ValidationError>),
+}
+
+/// Parses a textual gate-level netlist into a `Circuit`, the input-side counterpart to
+/// `stringify_circuit` (whose `Name = expr` output is not itself accepted here).
+///
+/// The format is line-oriented:
+/// ```text
+/// INPUT A
+/// INPUT B
+/// AND g1(out=w1, a=A, b=B)
+/// NOT g2(out=w2, a=w1)
+/// OUTPUT C = w2
+/// ```
+/// Every net has to be driven - by an `INPUT` or a gate's `out` port - before it's referenced
+/// as a gate input or an `OUTPUT`'s source; forward references aren't supported. Blank lines
+/// and lines starting with `//` or `#` are ignored. The resulting circuit is run through
+/// `Circuit::validate` before it's handed back.
+pub fn parse_netlist(source: &str) -> Result<Circuit, ParseError> {
+    let mut circuit = Circuit::new();
+    let mut nets: HashMap<String, NodeIndex> = HashMap::new();
+
+    for (line_number, line) in source.lines().enumerate() {
+        let line_number = line_number + 1;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix("INPUT ") {
+            let name = name.trim();
+            let component = circuit.add_component(name, ComponentKind::Input);
+            insert_net(&mut nets, name, component.index, line_number, column_of(line, name))?;
+        } else if let Some(rest) = trimmed.strip_prefix("OUTPUT ") {
+            let (name, net) = split_once_trimmed(rest, '=').ok_or_else(|| {
+                syntax_error(line_number, column_of(line, rest), "expected `OUTPUT <name> = <net>`")
+            })?;
+            let source_index = *nets.get(net).ok_or_else(|| {
+                syntax_error(line_number, column_of(line, net), format!("undefined net `{}`", net))
+            })?;
+            let component = circuit.add_component(name, ComponentKind::Output);
+            circuit.add_connection(&wrap(source_index), &component);
+        } else {
+            parse_gate(&mut circuit, &mut nets, trimmed, line, line_number)?;
+        }
+    }
+
+    circuit.validate().map_err(ParseError::Invalid)?;
+    Ok(circuit)
+}
+
+/// The 1-based column at which `token` appears within `line`. `token` must actually be a slice of
+/// `line` (as produced by trimming or further slicing it, never a freshly built `String`), so its
+/// position can be read straight off the pointers instead of re-searching the text.
+fn column_of(line: &str, token: &str) -> usize {
+    (token.as_ptr() as usize - line.as_ptr() as usize) + 1
+}
+
+/// Parses a `KIND name(out=net, port=net, ...)` gate instance line and wires it into `circuit`.
+/// `statement` is the trimmed line to parse; `line` is the untrimmed original, used only to
+/// compute the column of whichever token turns out to be the problem.
+fn parse_gate(
+    circuit: &mut Circuit,
+    nets: &mut HashMap<String, NodeIndex>,
+    statement: &str,
+    line: &str,
+    line_number: usize,
+) -> Result<(), ParseError> {
+    let (kind_text, rest) = statement.split_once(' ').ok_or_else(|| {
+        syntax_error(line_number, column_of(line, statement), "expected `<KIND> <name>(...)`")
+    })?;
+
+    let kind = match kind_text {
+        "AND" => ComponentKind::And,
+        "OR" => ComponentKind::Or,
+        "NOT" => ComponentKind::Not,
+        _ => {
+            return Err(syntax_error(
+                line_number,
+                column_of(line, kind_text),
+                format!("unknown gate kind `{}`", kind_text),
+            ))
+        }
+    };
+
+    let rest = rest.trim();
+    let open = rest
+        .find('(')
+        .ok_or_else(|| syntax_error(line_number, column_of(line, rest), "expected `(` after gate name"))?;
+    let close = rest
+        .rfind(')')
+        .ok_or_else(|| syntax_error(line_number, column_of(line, rest), "expected closing `)`"))?;
+
+    if open >= close {
+        return Err(syntax_error(line_number, column_of(line, rest), "`)` appears before `(`"));
+    }
+
+    let name = rest[..open].trim();
+    let ports = &rest[open + 1..close];
+
+    let gate = circuit.add_component(name, kind);
+    let mut out_net = None;
+
+    for port in ports.split(',') {
+        let port = port.trim();
+        if port.is_empty() {
+            continue;
+        }
+
+        let (key, value) = split_once_trimmed(port, '=').ok_or_else(|| {
+            syntax_error(line_number, column_of(line, port), format!("expected `port=net`, found `{}`", port))
+        })?;
+
+        if key == "out" {
+            out_net = Some(value);
+        } else {
+            let source_index = *nets.get(value).ok_or_else(|| {
+                syntax_error(line_number, column_of(line, value), format!("undefined net `{}`", value))
+            })?;
+            circuit.add_connection(&wrap(source_index), &gate);
+        }
+    }
+
+    let out_net = out_net
+        .ok_or_else(|| syntax_error(line_number, column_of(line, ports), "gate is missing an `out` port"))?;
+
+    insert_net(nets, out_net, gate.index, line_number, column_of(line, out_net))
+}
+
+/// Records that `name` is now driven by `index`, rejecting a net that's already driven
+fn insert_net(
+    nets: &mut HashMap<String, NodeIndex>,
+    name: &str,
+    index: NodeIndex,
+    line: usize,
+    column: usize,
+) -> Result<(), ParseError> {
+    if nets.insert(name.to_string(), index).is_some() {
+        return Err(syntax_error(
+            line,
+            column,
+            format!("net `{}` is driven more than once", name),
+        ));
+    }
+    Ok(())
+}
+
+fn split_once_trimmed(text: &str, separator: char) -> Option<(&str, &str)> {
+    let (left, right) = text.split_once(separator)?;
+    Some((left.trim(), right.trim()))
+}
+
+fn syntax_error(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+    ParseError::Syntax {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+fn wrap(index: NodeIndex) -> Component {
+    Component { index }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::stringify::stringify_circuit;
+    use super::*;
+
+    #[test]
+    fn parses_gates_and_roundtrips_through_stringify() {
+        let circuit = parse_netlist(
+            "INPUT A\nINPUT B\nINPUT C\nOR g1(out=w1, a=B, b=C)\nAND g2(out=w2, a=A, b=w1)\nOUTPUT D = w2",
+        )
+        .unwrap();
+
+        assert_eq!(stringify_circuit(&circuit).unwrap(), "D = A(B + C)");
+    }
+
+    #[test]
+    fn ignores_blank_lines_and_comments() {
+        let circuit = parse_netlist(
+            "// a single inverter\nINPUT A\n\n# comment\nNOT g1(out=w1, a=A)\nOUTPUT B = w1",
+        )
+        .unwrap();
+
+        assert_eq!(stringify_circuit(&circuit).unwrap(), "B = !A");
+    }
+
+    #[test]
+    fn rejects_reference_to_an_undefined_net() {
+        let source = "INPUT A\nNOT g1(out=w1, a=missing)";
+        let error = parse_netlist(source).unwrap_err();
+
+        match error {
+            ParseError::Syntax { line, column, .. } => {
+                assert_eq!(line, 2);
+                // The column should point at `missing`, not just the start of the line
+                let offending_line = source.lines().nth(1).unwrap();
+                assert_eq!(&offending_line[column - 1..column - 1 + "missing".len()], "missing");
+            }
+            ParseError::Invalid(errors) => panic!("expected a syntax error, got {:?}", errors),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_gate_syntax() {
+        let error = parse_netlist("INPUT A\nNOT g1 a=A)").unwrap_err();
+
+        assert!(matches!(error, ParseError::Syntax { line: 2, .. }));
+    }
+
+    #[test]
+    fn column_of_missing_out_port_points_past_the_indentation() {
+        let source = "INPUT A\n    NOT g1(a=A)";
+        let error = parse_netlist(source).unwrap_err();
+
+        match error {
+            ParseError::Syntax { line, column, message } => {
+                assert_eq!(line, 2);
+                assert!(message.contains("out"));
+                // The indentation alone is 4 columns wide; the ports list starts well past it
+                assert!(column > 4, "column {} should point at the port list, not the indentation", column);
+            }
+            ParseError::Invalid(errors) => panic!("expected a syntax error, got {:?}", errors),
+        }
+    }
+
+    #[test]
+    fn rejects_a_closing_paren_before_the_opening_one() {
+        let error = parse_netlist("INPUT A\nAND g1)a=A,b=A(").unwrap_err();
+
+        assert!(matches!(error, ParseError::Syntax { line: 2, .. }));
+    }
+
+    #[test]
+    fn rejects_a_circuit_that_fails_validation() {
+        // The output reuses the input's name - valid syntax and distinct nets, but
+        // `Circuit::validate` rejects the duplicate component name.
+        let error = parse_netlist("INPUT A\nNOT g1(out=w1, a=A)\nOUTPUT A = w1").unwrap_err();
+
+        match error {
+            ParseError::Invalid(errors) => {
+                assert_eq!(errors.len(), 1);
+            }
+            ParseError::Syntax { message, .. } => panic!("expected a validation error, got {}", message),
+        }
+    }
+}
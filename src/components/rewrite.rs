@@ -0,0 +1,488 @@
+use super::{Circuit, Component, ComponentData, ComponentKind};
+use petgraph::{graph::NodeIndex, Directed, Graph, Incoming, Outgoing};
+use std::collections::{HashMap, HashSet};
+
+/// A small gate subgraph to search for inside a `Circuit`, with numbered wildcard leaves that
+/// bind to whatever concrete node sits in that position
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Matches any single node, binding it to the numbered slot
+    Wildcard(usize),
+    /// Matches a gate of the given kind whose children match each of the subpatterns. For the
+    /// commutative `And`/`Or` kinds, children may match in any order.
+    Gate(ComponentKind, Vec<Pattern>),
+}
+
+/// The concrete node each wildcard slot bound to in a successful match
+pub type Bindings = HashMap<usize, NodeIndex>;
+
+/// A local algebraic simplification: a `Pattern` to search for, and a replacement built from the
+/// resulting `Bindings`
+pub struct Rewrite {
+    name: &'static str,
+    pattern: Pattern,
+    build: fn(&mut Circuit, &Bindings) -> NodeIndex,
+}
+
+impl Rewrite {
+    fn new(
+        name: &'static str,
+        pattern: Pattern,
+        build: fn(&mut Circuit, &Bindings) -> NodeIndex,
+    ) -> Self {
+        Self {
+            name,
+            pattern,
+            build,
+        }
+    }
+
+    /// The rule's name, e.g. for logging which rewrite fired
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Tries to match this rule's pattern with `root` as the pattern's root node, returning the
+    /// bindings and every interior (non-wildcard) node the match covers, root included
+    fn match_at(
+        &self,
+        graph: &Graph<ComponentData, (), Directed>,
+        root: NodeIndex,
+    ) -> Option<(Bindings, Vec<NodeIndex>)> {
+        let mut bindings = Bindings::new();
+        let mut interior = Vec::new();
+
+        if !try_match(graph, &self.pattern, root, &mut bindings, &mut interior) {
+            return None;
+        }
+
+        // Every interior node other than the root must have no consumers outside the match, or
+        // rewriting through it would silently change behavior for whoever else reads it
+        if interior[1..]
+            .iter()
+            .any(|&node| graph.neighbors_directed(node, Outgoing).count() != 1)
+        {
+            return None;
+        }
+
+        Some((bindings, interior))
+    }
+}
+
+fn same_kind(a: ComponentKind, b: ComponentKind) -> bool {
+    matches!(
+        (a, b),
+        (ComponentKind::Not, ComponentKind::Not)
+            | (ComponentKind::And, ComponentKind::And)
+            | (ComponentKind::Or, ComponentKind::Or)
+            | (ComponentKind::Input, ComponentKind::Input)
+            | (ComponentKind::Output, ComponentKind::Output)
+    )
+}
+
+fn try_match(
+    graph: &Graph<ComponentData, (), Directed>,
+    pattern: &Pattern,
+    node: NodeIndex,
+    bindings: &mut Bindings,
+    interior: &mut Vec<NodeIndex>,
+) -> bool {
+    match pattern {
+        Pattern::Wildcard(slot) => match bindings.get(slot) {
+            Some(&bound) => bound == node,
+            None => {
+                bindings.insert(*slot, node);
+                true
+            }
+        },
+        Pattern::Gate(kind, children) => {
+            if !same_kind(graph[node].kind, *kind) {
+                return false;
+            }
+
+            let candidates: Vec<NodeIndex> = graph.neighbors_directed(node, Incoming).collect();
+            if candidates.len() != children.len() {
+                return false;
+            }
+
+            interior.push(node);
+            match_children(graph, children, &candidates, bindings, interior)
+        }
+    }
+}
+
+/// Tries every assignment of `candidates` to `patterns` via backtracking - this makes the match
+/// order-independent, which is what lets a commutative gate's children match in any order.
+/// Bindings and interior nodes recorded by a failed attempt are rolled back before the next one.
+fn match_children(
+    graph: &Graph<ComponentData, (), Directed>,
+    patterns: &[Pattern],
+    candidates: &[NodeIndex],
+    bindings: &mut Bindings,
+    interior: &mut Vec<NodeIndex>,
+) -> bool {
+    let Some((first, rest)) = patterns.split_first() else {
+        return true;
+    };
+
+    for (position, &candidate) in candidates.iter().enumerate() {
+        let mut remaining_candidates = candidates.to_vec();
+        remaining_candidates.remove(position);
+
+        let saved_bindings = bindings.clone();
+        let saved_interior_len = interior.len();
+
+        if try_match(graph, first, candidate, bindings, interior)
+            && match_children(graph, rest, &remaining_candidates, bindings, interior)
+        {
+            return true;
+        }
+
+        *bindings = saved_bindings;
+        interior.truncate(saved_interior_len);
+    }
+
+    false
+}
+
+fn wrap(index: NodeIndex) -> Component {
+    Component { index }
+}
+
+/// Generates a name that doesn't collide with any component already in `circuit`
+fn fresh_name(circuit: &Circuit, prefix: &str) -> String {
+    let existing: HashSet<&str> = circuit.graph().node_weights().map(|data| data.name.as_str()).collect();
+
+    let mut suffix = 1;
+    loop {
+        let candidate = format!("{}_{}", prefix, suffix);
+        if !existing.contains(candidate.as_str()) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+/// The crate's built-in algebraic simplification rules
+pub fn default_rules() -> Vec<Rewrite> {
+    vec![
+        Rewrite::new(
+            "double_negation",
+            Pattern::Gate(
+                ComponentKind::Not,
+                vec![Pattern::Gate(ComponentKind::Not, vec![Pattern::Wildcard(0)])],
+            ),
+            |_circuit, bindings| bindings[&0],
+        ),
+        Rewrite::new(
+            "de_morgan_and",
+            Pattern::Gate(
+                ComponentKind::Not,
+                vec![Pattern::Gate(
+                    ComponentKind::And,
+                    vec![Pattern::Wildcard(0), Pattern::Wildcard(1)],
+                )],
+            ),
+            |circuit, bindings| negate_each(circuit, bindings, ComponentKind::Or),
+        ),
+        Rewrite::new(
+            "de_morgan_or",
+            Pattern::Gate(
+                ComponentKind::Not,
+                vec![Pattern::Gate(
+                    ComponentKind::Or,
+                    vec![Pattern::Wildcard(0), Pattern::Wildcard(1)],
+                )],
+            ),
+            |circuit, bindings| negate_each(circuit, bindings, ComponentKind::And),
+        ),
+        Rewrite::new(
+            "idempotence_and",
+            Pattern::Gate(
+                ComponentKind::And,
+                vec![Pattern::Wildcard(0), Pattern::Wildcard(0)],
+            ),
+            |_circuit, bindings| bindings[&0],
+        ),
+        Rewrite::new(
+            "idempotence_or",
+            Pattern::Gate(ComponentKind::Or, vec![Pattern::Wildcard(0), Pattern::Wildcard(0)]),
+            |_circuit, bindings| bindings[&0],
+        ),
+        Rewrite::new(
+            "absorption_and_or",
+            Pattern::Gate(
+                ComponentKind::And,
+                vec![
+                    Pattern::Wildcard(0),
+                    Pattern::Gate(ComponentKind::Or, vec![Pattern::Wildcard(0), Pattern::Wildcard(1)]),
+                ],
+            ),
+            |_circuit, bindings| bindings[&0],
+        ),
+        Rewrite::new(
+            "absorption_or_and",
+            Pattern::Gate(
+                ComponentKind::Or,
+                vec![
+                    Pattern::Wildcard(0),
+                    Pattern::Gate(ComponentKind::And, vec![Pattern::Wildcard(0), Pattern::Wildcard(1)]),
+                ],
+            ),
+            |_circuit, bindings| bindings[&0],
+        ),
+    ]
+}
+
+/// Builds `!a op !b` (De Morgan's law in either direction), returning the new top-level node
+fn negate_each(circuit: &mut Circuit, bindings: &Bindings, joiner: ComponentKind) -> NodeIndex {
+    let a = wrap(bindings[&0]);
+    let b = wrap(bindings[&1]);
+
+    let not_a = circuit.add_component(fresh_name(circuit, "NOT"), ComponentKind::Not);
+    circuit.add_connection(&a, &not_a);
+    let not_b = circuit.add_component(fresh_name(circuit, "NOT"), ComponentKind::Not);
+    circuit.add_connection(&b, &not_b);
+
+    let joined = circuit.add_component(fresh_name(circuit, "JOIN"), joiner);
+    circuit.add_connection(&not_a, &joined);
+    circuit.add_connection(&not_b, &joined);
+
+    joined.index
+}
+
+fn apply_first_match(circuit: &mut Circuit, rules: &[Rewrite]) -> bool {
+    for rule in rules {
+        let nodes: Vec<NodeIndex> = circuit.graph().node_indices().collect();
+
+        for node in nodes {
+            if let Some((bindings, interior)) = rule.match_at(circuit.graph(), node) {
+                let replacement = (rule.build)(circuit, &bindings);
+                circuit.splice(node, replacement, interior);
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+impl Circuit {
+    /// Repeatedly applies the default algebraic rewrite rules until none of them match anymore
+    pub fn simplify(&mut self) {
+        let rules = default_rules();
+        while apply_first_match(self, &rules) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_negation_is_eliminated() {
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+        let not1 = circuit.add_component("NOT_1", ComponentKind::Not);
+        let not2 = circuit.add_component("NOT_2", ComponentKind::Not);
+
+        circuit.add_connection(&input, &not1);
+        circuit.add_connection(&not1, &not2);
+        circuit.add_connection(&not2, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            2,
+            "both NOT gates should have been eliminated"
+        );
+    }
+
+    #[test]
+    fn de_morgan_rewrites_not_of_and() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+        let not = circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&b, &and);
+        circuit.add_connection(&and, &not);
+        circuit.add_connection(&not, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+    }
+
+    #[test]
+    fn de_morgan_rewrites_not_of_or() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+        let not = circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&b, &or);
+        circuit.add_connection(&or, &not);
+        circuit.add_connection(&not, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+    }
+
+    #[test]
+    fn idempotence_and_collapses_a_and_a() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&and, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            2,
+            "the AND gate should have been eliminated"
+        );
+    }
+
+    #[test]
+    fn idempotence_or_collapses_a_or_a() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&or, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            2,
+            "the OR gate should have been eliminated"
+        );
+    }
+
+    #[test]
+    fn absorption_simplifies_a_and_a_or_b() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&b, &or);
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&or, &and);
+        circuit.add_connection(&and, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            3,
+            "A, B and the output should be all that's left"
+        );
+    }
+
+    #[test]
+    fn absorption_simplifies_a_or_a_and_b() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&b, &and);
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&and, &or);
+        circuit.add_connection(&or, &output);
+
+        let before = circuit.truth_table();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            3,
+            "A, B and the output should be all that's left"
+        );
+    }
+
+    #[test]
+    fn shared_fan_out_blocks_the_rewrite() {
+        // The inner OR also feeds a second output directly, so collapsing the absorption
+        // pattern through it would silently change that output's value too - it must be left
+        // alone.
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let or_output = circuit.add_component("D", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+
+        circuit.add_connection(&a, &or);
+        circuit.add_connection(&b, &or);
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&or, &and);
+        circuit.add_connection(&and, &output);
+        circuit.add_connection(&or, &or_output);
+
+        let before = circuit.truth_table();
+        let node_count_before = circuit.graph().node_count();
+        circuit.simplify();
+        let after = circuit.truth_table();
+
+        assert_eq!(before, after);
+        circuit.validate().unwrap();
+        assert_eq!(
+            circuit.graph().node_count(),
+            node_count_before,
+            "the rewrite should have been skipped since OR_1 has an outside consumer"
+        );
+    }
+}
@@ -1,9 +1,124 @@
-use super::Circuit;
+use super::{Circuit, ComponentData, ComponentKind};
+use petgraph::{graph::NodeIndex, visit::IntoNodeReferences, Incoming};
+use std::collections::HashSet;
+
+/// How tightly a rendered subexpression binds, from loosest to tightest.
+///
+/// A child only needs parentheses when it binds more loosely than the
+/// operator it's being nested inside of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Or,
+    And,
+    Not,
+    Atom,
+}
 
 /// Creates a string representation of the circuit
 /// Tries to keep in in alphabetical order
-pub fn stringify_circuit(_circuit: &Circuit) -> Result<String, String> {
-    Ok(String::from("This is a potato"))
+pub fn stringify_circuit(circuit: &Circuit) -> Result<String, String> {
+    let graph = circuit.graph();
+
+    let mut lines: Vec<(String, String)> = graph
+        .node_references()
+        .filter(|(_, data)| matches!(data.kind, ComponentKind::Output))
+        .map(|(index, data)| {
+            let (expr, _) = render(graph, index, &mut HashSet::new())?;
+            Ok((data.name.clone(), expr))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    lines.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(lines
+        .into_iter()
+        .map(|(name, expr)| format!("{} = {}", name, expr))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Renders the expression feeding into `index`, returning the rendered string along with the
+/// precedence of its outermost operator
+///
+/// `path` holds every node still on the current recursion stack, so a node that's revisited
+/// while it's still an ancestor of itself means the circuit has a combinational cycle - rather
+/// than recursing unbounded into a stack overflow, that's reported as an `Err`.
+fn render(
+    graph: &petgraph::Graph<ComponentData, (), petgraph::Directed>,
+    index: NodeIndex,
+    path: &mut HashSet<NodeIndex>,
+) -> Result<(String, Precedence), String> {
+    let data = &graph[index];
+
+    if !path.insert(index) {
+        return Err(format!("{} is part of a combinational cycle", data.name));
+    }
+
+    let result = match data.kind {
+        ComponentKind::Input => Ok((data.name.clone(), Precedence::Atom)),
+        ComponentKind::Output | ComponentKind::Not => {
+            let mut incoming = graph.neighbors_directed(index, Incoming);
+            let child = incoming
+                .next()
+                .ok_or_else(|| format!("{} has no input connected to it", data.name));
+
+            child.and_then(|child| {
+                let (child_str, child_precedence) = render(graph, child, path)?;
+
+                match data.kind {
+                    ComponentKind::Output => Ok((child_str, child_precedence)),
+                    ComponentKind::Not => {
+                        let child_str = parenthesize_if_needed(child_str, child_precedence, Precedence::Not);
+                        Ok((format!("!{}", child_str), Precedence::Not))
+                    }
+                    _ => unreachable!(),
+                }
+            })
+        }
+        ComponentKind::And | ComponentKind::Or => {
+            let precedence = match data.kind {
+                ComponentKind::And => Precedence::And,
+                ComponentKind::Or => Precedence::Or,
+                _ => unreachable!(),
+            };
+
+            graph
+                .neighbors_directed(index, Incoming)
+                .map(|child| render(graph, child, path))
+                .collect::<Result<Vec<_>, String>>()
+                .map(|mut operands| {
+                    operands.sort_by(|a, b| a.0.cmp(&b.0));
+
+                    let operands = operands
+                        .into_iter()
+                        .map(|(child_str, child_precedence)| {
+                            parenthesize_if_needed(child_str, child_precedence, precedence)
+                        })
+                        .collect::<Vec<_>>();
+
+                    let joined = match data.kind {
+                        ComponentKind::And => operands.join(""),
+                        ComponentKind::Or => operands.join(" + "),
+                        _ => unreachable!(),
+                    };
+
+                    (joined, precedence)
+                })
+        }
+    };
+
+    path.remove(&index);
+
+    result
+}
+
+/// Wraps `expr` in parentheses if it binds more loosely than the operator it's nested inside of
+fn parenthesize_if_needed(expr: String, expr_precedence: Precedence, parent_precedence: Precedence) -> String {
+    if expr_precedence < parent_precedence {
+        format!("({})", expr)
+    } else {
+        expr
+    }
 }
 
 #[cfg(test)]
@@ -56,4 +171,83 @@ mod tests {
             String::from("C = A + B")
         );
     }
+
+    #[test]
+    fn multiple_outputs_are_sorted_alphabetically() {
+        let mut circuit = Circuit::new();
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let output_b = circuit.add_component("B", ComponentKind::Output);
+        let output_a = circuit.add_component("A_OUT", ComponentKind::Output);
+        let not = circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.add_connection(&input, &not);
+        circuit.add_connection(&not, &output_b);
+        circuit.add_connection(&input, &output_a);
+
+        assert_eq!(
+            stringify_circuit(&circuit).unwrap(),
+            String::from("A_OUT = A\nB = !A")
+        );
+    }
+
+    #[test]
+    fn and_of_or_is_parenthesized() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let c = circuit.add_component("C", ComponentKind::Input);
+        let output = circuit.add_component("D", ComponentKind::Output);
+        let or = circuit.add_component("OR_1", ComponentKind::Or);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+
+        circuit.add_connection(&b, &or);
+        circuit.add_connection(&c, &or);
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&or, &and);
+        circuit.add_connection(&and, &output);
+
+        assert_eq!(
+            stringify_circuit(&circuit).unwrap(),
+            String::from("D = A(B + C)")
+        );
+    }
+
+    #[test]
+    fn combinational_cycle_is_reported_instead_of_overflowing_the_stack() {
+        let mut circuit = Circuit::new();
+        let and1 = circuit.add_component("AND_1", ComponentKind::And);
+        let and2 = circuit.add_component("AND_2", ComponentKind::And);
+        let input = circuit.add_component("A", ComponentKind::Input);
+        let output = circuit.add_component("B", ComponentKind::Output);
+
+        // AND_1 feeds back into AND_2, which feeds back into AND_1
+        circuit.add_connection(&input, &and1);
+        circuit.add_connection(&and2, &and1);
+        circuit.add_connection(&and1, &and2);
+        circuit.add_connection(&input, &and2);
+        circuit.add_connection(&and1, &output);
+
+        let error = stringify_circuit(&circuit).unwrap_err();
+        assert!(error.contains("combinational cycle"), "{}", error);
+    }
+
+    #[test]
+    fn not_of_and_is_parenthesized() {
+        let mut circuit = Circuit::new();
+        let a = circuit.add_component("A", ComponentKind::Input);
+        let b = circuit.add_component("B", ComponentKind::Input);
+        let output = circuit.add_component("C", ComponentKind::Output);
+        let and = circuit.add_component("AND_1", ComponentKind::And);
+        let not = circuit.add_component("NOT_1", ComponentKind::Not);
+
+        circuit.add_connection(&a, &and);
+        circuit.add_connection(&b, &and);
+        circuit.add_connection(&and, &not);
+        circuit.add_connection(&not, &output);
+
+        assert_eq!(
+            stringify_circuit(&circuit).unwrap(),
+            String::from("C = !(AB)")
+        );
+    }
 }